@@ -0,0 +1,49 @@
+use amethyst_core::{
+    ecs::prelude::{Component, DenseVecStorage},
+    math::Vector3,
+};
+use palette::Srgb;
+use serde::{Deserialize, Serialize};
+
+/// A light source. Each variant wraps the full data a lighting/PBR pass needs to shade
+/// with it; `visibility.rs` additionally reads `radius`/`angle` off these to decide how
+/// far a light's shadow-caster culling volume reaches.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Light {
+    Point(PointLight),
+    Directional(DirectionalLight),
+    Spot(SpotLight),
+}
+
+/// An omnidirectional light falling off over `radius`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PointLight {
+    pub color: Srgb,
+    pub intensity: f32,
+    pub radius: f32,
+    pub smoothness: f32,
+}
+
+/// A light with parallel rays, such as the sun, with no distance falloff.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DirectionalLight {
+    pub color: Srgb,
+    pub direction: Vector3<f32>,
+    pub intensity: f32,
+}
+
+/// A cone-shaped light falling off over `range`, with its half-angle (measured from the
+/// light's forward axis to the cone's edge) given by `angle`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SpotLight {
+    pub angle: f32,
+    pub color: Srgb,
+    pub direction: Vector3<f32>,
+    pub intensity: f32,
+    pub range: f32,
+    pub smoothness: f32,
+}
+
+impl Component for Light {
+    type Storage = DenseVecStorage<Self>;
+}
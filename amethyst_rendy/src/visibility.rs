@@ -1,18 +1,21 @@
 use crate::{
     camera::{ActiveCamera, Camera},
     hidden::{Hidden, HiddenPropagate},
+    light::Light,
     transparent::Transparent,
 };
 use amethyst_core::{
     ecs::prelude::{
         Component, DenseVecStorage, Entities, Entity, Join, Read, ReadStorage, System, Write,
     },
-    math::{distance_squared, Matrix4, Point3, Vector4},
+    math::{distance_squared, Matrix4, Orthographic3, Perspective3, Point3, Vector3, Vector4},
     GlobalTransform,
 };
 use hibitset::BitSet;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::f32::consts::FRAC_PI_2;
 
 #[cfg(feature = "profiler")]
 use thread_profiler::profile_scope;
@@ -70,6 +73,58 @@ impl Component for BoundingSphere {
     type Storage = DenseVecStorage<Self>;
 }
 
+/// Defines an object's local-space axis-aligned bounding box used by frustum culling.
+/// More accurate than `BoundingSphere` for elongated or non-uniformly scaled meshes,
+/// at the cost of a slightly pricier per-plane test.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct BoundingBox {
+    pub center: Point3<f32>,
+    pub half_extents: Vector3<f32>,
+}
+
+impl Default for BoundingBox {
+    fn default() -> Self {
+        Self {
+            center: Point3::origin(),
+            half_extents: Vector3::new(0.5, 0.5, 0.5),
+        }
+    }
+}
+
+impl BoundingBox {
+    pub fn new(center: Point3<f32>, half_extents: Vector3<f32>) -> Self {
+        Self {
+            center,
+            half_extents,
+        }
+    }
+
+    /// Computes this box's world-space center and half-extents under `transform`. Unlike
+    /// scaling a bounding sphere's radius by the largest diagonal entry, this accounts
+    /// correctly for rotation and non-uniform scale by summing the absolute values of
+    /// the transform's upper-left 3x3 applied to the local half-extents.
+    pub fn world_extents(&self, transform: &GlobalTransform) -> (Point3<f32>, Vector3<f32>) {
+        let m = &transform.0;
+        let center = m.transform_point(&self.center);
+        let extents = Vector3::new(
+            m[(0, 0)].abs() * self.half_extents.x
+                + m[(0, 1)].abs() * self.half_extents.y
+                + m[(0, 2)].abs() * self.half_extents.z,
+            m[(1, 0)].abs() * self.half_extents.x
+                + m[(1, 1)].abs() * self.half_extents.y
+                + m[(1, 2)].abs() * self.half_extents.z,
+            m[(2, 0)].abs() * self.half_extents.x
+                + m[(2, 1)].abs() * self.half_extents.y
+                + m[(2, 2)].abs() * self.half_extents.z,
+        );
+        (center, extents)
+    }
+}
+
+impl Component for BoundingBox {
+    type Storage = DenseVecStorage<Self>;
+}
+
 #[derive(Clone)]
 struct Internals {
     entity: Entity,
@@ -99,11 +154,23 @@ impl<'a> System<'a> for VisibilitySortingSystem {
         ReadStorage<'a, Transparent>,
         ReadStorage<'a, GlobalTransform>,
         ReadStorage<'a, BoundingSphere>,
+        ReadStorage<'a, BoundingBox>,
     );
 
     fn run(
         &mut self,
-        (entities, mut visibility, hidden, hidden_prop, active, camera, transparent, global, bound): Self::SystemData,
+        (
+            entities,
+            mut visibility,
+            hidden,
+            hidden_prop,
+            active,
+            camera,
+            transparent,
+            global,
+            bound,
+            bbox,
+        ): Self::SystemData,
     ) {
         #[cfg(feature = "profiler")]
         profile_scope!("run");
@@ -123,23 +190,41 @@ impl<'a> System<'a> for VisibilitySortingSystem {
 
         self.centroids.clear();
         self.centroids.extend(
-            (&*entities, &global, bound.maybe(), !&hidden, !&hidden_prop)
+            (
+                &*entities,
+                &global,
+                bound.maybe(),
+                bbox.maybe(),
+                !&hidden,
+                !&hidden_prop,
+            )
                 .join()
-                .map(|(entity, global, sphere, _, _)| {
-                    let pos = sphere.map_or(&origin, |s| &s.center);
-                    (
+                .filter_map(|(entity, global, sphere, bbox, _, _)| {
+                    // Entities with a `BoundingBox` are culled against its exact
+                    // world-space extents; everything else falls back to the cheaper
+                    // (but less accurate under rotation/non-uniform scale) sphere test.
+                    let (visible, centroid) = match bbox {
+                        Some(bbox) => {
+                            let (center, extents) = bbox.world_extents(global);
+                            (frustum.check_aabb(&center, &extents), center)
+                        }
+                        None => {
+                            let pos = sphere.map_or(&origin, |s| &s.center);
+                            let centroid = global.0.transform_point(pos);
+                            let radius = sphere.map_or(1.0, |s| s.radius)
+                                * global.0[(0, 0)].max(global.0[(1, 1)]).max(global.0[(2, 2)]);
+                            (frustum.check_sphere(&centroid, radius), centroid)
+                        }
+                    };
+                    if !visible {
+                        return None;
+                    }
+                    Some(Internals {
                         entity,
-                        global.0.transform_point(&pos),
-                        sphere.map_or(1.0, |s| s.radius)
-                            * global.0[(0, 0)].max(global.0[(1, 1)]).max(global.0[(2, 2)]),
-                    )
-                })
-                .filter(|(_, centroid, radius)| frustum.check_sphere(centroid, *radius))
-                .map(|(entity, centroid, _)| Internals {
-                    entity,
-                    transparent: transparent.contains(entity),
-                    centroid,
-                    camera_distance: distance_squared(&centroid, &camera_centroid),
+                        transparent: transparent.contains(entity),
+                        centroid,
+                        camera_distance: distance_squared(&centroid, &camera_centroid),
+                    })
                 }),
         );
         self.transparent.clear();
@@ -167,8 +252,11 @@ impl<'a> System<'a> for VisibilitySortingSystem {
     }
 }
 
+/// A view frustum's six clip planes, used to cull bounding volumes. Exposed so that
+/// resources like `ShadowCascades` can hand out per-cascade frusta for a renderer to
+/// query directly.
 #[derive(Debug)]
-struct Frustum {
+pub struct Frustum {
     planes: [Vector4<f32>; 6],
 }
 
@@ -194,7 +282,7 @@ impl Frustum {
         }
     }
 
-    fn check_sphere(&self, center: &Point3<f32>, radius: f32) -> bool {
+    pub fn check_sphere(&self, center: &Point3<f32>, radius: f32) -> bool {
         for plane in &self.planes {
             if plane.xyz().dot(&center.coords) + plane.w <= -radius {
                 return false;
@@ -202,4 +290,692 @@ impl Frustum {
         }
         return true;
     }
+
+    /// Tests a world-space AABB (given by `center` and `half_extents`) against all six
+    /// planes using the positive-vertex method: for each plane, only the box corner
+    /// farthest along the plane's normal can be outside it, so a single dot product per
+    /// plane is enough to decide rejection.
+    pub fn check_aabb(&self, center: &Point3<f32>, half_extents: &Vector3<f32>) -> bool {
+        for plane in &self.planes {
+            let normal = plane.xyz();
+            let p_vertex_distance = half_extents.x * normal.x.abs()
+                + half_extents.y * normal.y.abs()
+                + half_extents.z * normal.z.abs();
+            if normal.dot(&center.coords) + plane.w <= -p_vertex_distance {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Decodes the near/far clip distances baked into a symmetric perspective projection
+/// matrix, since `Camera` only exposes the composed `proj` matrix. Assumes the
+/// zero-to-one depth convention `Camera`'s wgpu/rendy-backed projections actually use
+/// (`m22 = far / (near - far)`, `m23 = near * far / (near - far)`), not nalgebra's
+/// `Perspective3` default of OpenGL-style `[-1, 1]` depth.
+///
+/// Returns `None` if `proj` isn't a perspective projection at all (e.g. an orthographic
+/// camera such as `Camera::standard_2d()`'s fallback): a perspective matrix's bottom row
+/// is `[0, 0, -1, 0]`, while an orthographic one's is `[0, 0, 0, 1]`, so `m33` alone tells
+/// the two apart.
+fn perspective_near_far(proj: &Matrix4<f32>) -> Option<(f32, f32)> {
+    if proj[(3, 3)].abs() > 1e-5 {
+        return None;
+    }
+    let a = proj[(2, 2)];
+    let b = proj[(2, 3)];
+    let near = b / a;
+    let far = b / (1.0 + a);
+    debug_assert!(
+        (near * far / (near - far) - b).abs() < 1e-3 * b.abs().max(1.0),
+        "perspective_near_far: proj matrix doesn't match the assumed zero-to-one depth convention"
+    );
+    Some((near, far))
+}
+
+/// Unprojects the four corners of an NDC-space rectangle at two view-space depths into
+/// view space, for a symmetric perspective projection. `ndc_x`/`ndc_y` are `[min, max]`
+/// in `[-1, 1]`; the camera looks down `-z`, so `z_near`/`z_far` are positive distances.
+fn unproject_rect(
+    proj: &Matrix4<f32>,
+    ndc_x: [f32; 2],
+    ndc_y: [f32; 2],
+    z_near: f32,
+    z_far: f32,
+) -> [Point3<f32>; 8] {
+    let x_scale = proj[(0, 0)];
+    let y_scale = proj[(1, 1)];
+    let mut corners = [Point3::origin(); 8];
+    let mut i = 0;
+    for &z in &[z_near, z_far] {
+        for &nx in &ndc_x {
+            for &ny in &ndc_y {
+                corners[i] = Point3::new(nx * z / x_scale, ny * z / y_scale, -z);
+                i += 1;
+            }
+        }
+    }
+    corners
+}
+
+/// Config controlling how a camera frustum is divided into clusters by
+/// `LightClusteringSystem`. Screen tiles are `tile_size` pixels square; depth slices are
+/// distributed exponentially so they pack tighter near the camera.
+#[derive(Debug, Clone, Copy)]
+pub struct ClusterConfig {
+    pub tile_size: u32,
+    pub z_slices: u32,
+    pub screen_size: (u32, u32),
+}
+
+impl Default for ClusterConfig {
+    fn default() -> Self {
+        Self {
+            tile_size: 16,
+            z_slices: 16,
+            screen_size: (1920, 1080),
+        }
+    }
+}
+
+impl ClusterConfig {
+    fn tiles_x(&self) -> u32 {
+        (self.screen_size.0 + self.tile_size - 1) / self.tile_size
+    }
+
+    fn tiles_y(&self) -> u32 {
+        (self.screen_size.1 + self.tile_size - 1) / self.tile_size
+    }
+}
+
+/// Per-cluster light index lists produced by `LightClusteringSystem`, consumed by a
+/// clustered/forward+ lighting pass.
+#[derive(Default)]
+pub struct ClusteredLights {
+    pub tiles_x: u32,
+    pub tiles_y: u32,
+    pub z_slices: u32,
+    /// Dense list of the lights visible to the camera this frame, in the same order a
+    /// renderer should upload them to its GPU light buffer. `light_indices` values are
+    /// indices into this list, not raw entity ids.
+    pub lights: Vec<Entity>,
+    /// `(offset, count)` into `light_indices`, indexed by
+    /// `x + y * tiles_x + z * tiles_x * tiles_y`.
+    pub cluster_ranges: Vec<(u32, u32)>,
+    /// Indices into `lights`, grouped by cluster per `cluster_ranges`.
+    pub light_indices: Vec<u32>,
+}
+
+impl ClusteredLights {
+    /// Light indices assigned to the cluster at tile `(x, y)` and depth slice `z`.
+    pub fn cluster_lights(&self, x: u32, y: u32, z: u32) -> &[u32] {
+        let index = (x + y * self.tiles_x + z * self.tiles_x * self.tiles_y) as usize;
+        let (offset, count) = self.cluster_ranges[index];
+        &self.light_indices[offset as usize..(offset + count) as usize]
+    }
+}
+
+struct ClusterAabb {
+    min: Point3<f32>,
+    max: Point3<f32>,
+}
+
+impl ClusterAabb {
+    fn distance_squared_to(&self, point: &Point3<f32>) -> f32 {
+        let dx = (self.min.x - point.x).max(0.0).max(point.x - self.max.x);
+        let dy = (self.min.y - point.y).max(0.0).max(point.y - self.max.y);
+        let dz = (self.min.z - point.z).max(0.0).max(point.z - self.max.z);
+        dx * dx + dy * dy + dz * dz
+    }
+}
+
+fn cluster_aabb(
+    proj: &Matrix4<f32>,
+    x: u32,
+    y: u32,
+    tiles_x: u32,
+    tiles_y: u32,
+    slice_near: f32,
+    slice_far: f32,
+) -> ClusterAabb {
+    let ndc_x = [
+        (x as f32 / tiles_x as f32) * 2.0 - 1.0,
+        ((x + 1) as f32 / tiles_x as f32) * 2.0 - 1.0,
+    ];
+    let ndc_y = [
+        (y as f32 / tiles_y as f32) * 2.0 - 1.0,
+        ((y + 1) as f32 / tiles_y as f32) * 2.0 - 1.0,
+    ];
+
+    let mut min = Point3::new(f32::MAX, f32::MAX, f32::MAX);
+    let mut max = Point3::new(f32::MIN, f32::MIN, f32::MIN);
+    for corner in &unproject_rect(proj, ndc_x, ndc_y, slice_near, slice_far) {
+        min = Point3::new(
+            min.x.min(corner.x),
+            min.y.min(corner.y),
+            min.z.min(corner.z),
+        );
+        max = Point3::new(
+            max.x.max(corner.x),
+            max.y.max(corner.y),
+            max.z.max(corner.z),
+        );
+    }
+    ClusterAabb { min, max }
+}
+
+/// Resolution, in texels, assumed for a cascade's shadow map when snapping its ortho
+/// bounds to avoid shimmering as the camera moves.
+const CASCADE_RESOLUTION: f32 = 2048.0;
+
+/// Config controlling how a directional light's shadow is split into cascades. `lambda`
+/// blends between a uniform split (`0.0`) and a logarithmic one (`1.0`); cascades near
+/// the camera benefit from the log split's tighter fit.
+#[derive(Debug, Clone, Copy)]
+pub struct CascadeConfig {
+    pub count: usize,
+    pub lambda: f32,
+}
+
+impl Default for CascadeConfig {
+    fn default() -> Self {
+        Self {
+            count: 4,
+            lambda: 0.5,
+        }
+    }
+}
+
+/// Per-cascade tight-fitting light-space orthographic frusta for a directional light,
+/// plus the view-space depth at each cascade boundary so the renderer can select a
+/// cascade by fragment depth.
+#[derive(Default)]
+pub struct ShadowCascades {
+    pub frusta: Vec<Frustum>,
+    pub splits: Vec<f32>,
+}
+
+/// Computes the `config.count + 1` cascade split distances (from `near` to `far`) using
+/// the practical log/uniform blend `d_i = lambda * log_i + (1 - lambda) * uniform_i`.
+fn cascade_splits(near: f32, far: f32, config: &CascadeConfig) -> Vec<f32> {
+    let mut splits = Vec::with_capacity(config.count + 1);
+    splits.push(near);
+    for i in 1..=config.count {
+        let t = i as f32 / config.count as f32;
+        let log = near * (far / near).powf(t);
+        let uniform = near + (far - near) * t;
+        splits.push(config.lambda * log + (1.0 - config.lambda) * uniform);
+    }
+    splits
+}
+
+/// Splits the camera's view depth range into cascades and builds a tight light-space
+/// orthographic `Frustum` for each, per the practical log/uniform split scheme.
+///
+/// Returns `None` if `camera` isn't a symmetric perspective projection (see
+/// `perspective_near_far`): cascades are a view-depth split, which isn't meaningful
+/// without one, e.g. when there's no active camera and callers fall back to
+/// `Camera::standard_2d()`'s orthographic projection.
+pub fn compute_cascades(
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    light_transform: &GlobalTransform,
+    config: &CascadeConfig,
+) -> Option<ShadowCascades> {
+    let (near, far) = perspective_near_far(&camera.proj)?;
+    let light_view = light_transform.0.try_inverse().unwrap();
+    let splits = cascade_splits(near, far, config);
+
+    let frusta = (0..config.count)
+        .map(|i| {
+            let corners = unproject_rect(
+                &camera.proj,
+                [-1.0, 1.0],
+                [-1.0, 1.0],
+                splits[i],
+                splits[i + 1],
+            );
+
+            let mut min = Point3::new(f32::MAX, f32::MAX, f32::MAX);
+            let mut max = Point3::new(f32::MIN, f32::MIN, f32::MIN);
+            for corner in &corners {
+                let world = camera_transform.0.transform_point(corner);
+                let light_space = light_view.transform_point(&world);
+                min = Point3::new(
+                    min.x.min(light_space.x),
+                    min.y.min(light_space.y),
+                    min.z.min(light_space.z),
+                );
+                max = Point3::new(
+                    max.x.max(light_space.x),
+                    max.y.max(light_space.y),
+                    max.z.max(light_space.z),
+                );
+            }
+
+            // Snap the origin to texel-sized increments so the cascade's bounds don't
+            // shift sub-texel amounts as the camera moves, which is what causes shadow
+            // shimmering.
+            let texel_size = (max.x - min.x).max(max.y - min.y) / CASCADE_RESOLUTION;
+            let snap = |v: f32| (v / texel_size).floor() * texel_size;
+            let (min_x, min_y) = (snap(min.x), snap(min.y));
+            let (max_x, max_y) = (min_x + (max.x - min.x), min_y + (max.y - min.y));
+
+            let proj =
+                Orthographic3::new(min_x, max_x, min_y, max_y, -max.z, -min.z).to_homogeneous();
+            Frustum::new(proj)
+        })
+        .collect();
+
+    Some(ShadowCascades { frusta, splits })
+}
+
+/// Divides the active camera's frustum into a grid of screen-tile x depth-slice
+/// clusters and assigns each visible light (an entity with a `BoundingSphere`) to every
+/// cluster its sphere overlaps, producing a `ClusteredLights` resource for a
+/// forward+/clustered PBR pass to consume.
+///
+/// Assumes the active camera (or the first camera found, if none is active) uses a
+/// symmetric perspective projection, since clustering slices its view depth range; if
+/// there's no such camera, `ClusteredLights` is left empty for the frame rather than
+/// built from a meaningless depth range.
+///
+/// Note that this should run after `GlobalTransform` has been updated for the current
+/// frame, and before rendering occurs.
+pub struct LightClusteringSystem {
+    lights: Vec<(Entity, Point3<f32>, f32)>,
+}
+
+impl LightClusteringSystem {
+    /// Create new light clustering system
+    pub fn new() -> Self {
+        LightClusteringSystem {
+            lights: Vec::default(),
+        }
+    }
+}
+
+impl Default for LightClusteringSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> System<'a> for LightClusteringSystem {
+    type SystemData = (
+        Entities<'a>,
+        Write<'a, ClusteredLights>,
+        Option<Read<'a, ActiveCamera>>,
+        Option<Read<'a, ClusterConfig>>,
+        ReadStorage<'a, Camera>,
+        ReadStorage<'a, GlobalTransform>,
+        ReadStorage<'a, BoundingSphere>,
+    );
+
+    fn run(
+        &mut self,
+        (entities, mut clustered, active, config, camera, global, bound): Self::SystemData,
+    ) {
+        #[cfg(feature = "profiler")]
+        profile_scope!("run");
+
+        let config = config.as_deref().copied().unwrap_or_default();
+        let defcam = Camera::standard_2d();
+        let identity = GlobalTransform::default();
+
+        let mut camera_join = (&camera, &global).join();
+        let (camera, camera_transform) = active
+            .and_then(|a| camera_join.get(a.entity, &entities))
+            .or_else(|| camera_join.next())
+            .unwrap_or((&defcam, &identity));
+
+        let view = camera_transform.0.try_inverse().unwrap();
+        let frustum = Frustum::new(camera.proj * view);
+        let (near, far) = match perspective_near_far(&camera.proj) {
+            Some(near_far) => near_far,
+            None => {
+                // No perspective camera to cluster against this frame (e.g. the only
+                // active camera is orthographic); leave the grid empty rather than
+                // slicing a depth range that doesn't exist.
+                clustered.tiles_x = 0;
+                clustered.tiles_y = 0;
+                clustered.z_slices = 0;
+                clustered.lights.clear();
+                clustered.cluster_ranges.clear();
+                clustered.light_indices.clear();
+                return;
+            }
+        };
+
+        // Reject lights against the coarse whole-frustum planes first, then transform
+        // the survivors into view space for the per-cluster AABB tests below.
+        self.lights.clear();
+        self.lights.extend(
+            (&*entities, &global, &bound)
+                .join()
+                .map(|(entity, global, sphere)| {
+                    let center = global.0.transform_point(&sphere.center);
+                    let radius = sphere.radius
+                        * global.0[(0, 0)].max(global.0[(1, 1)]).max(global.0[(2, 2)]);
+                    (entity, center, radius)
+                })
+                .filter(|(_, center, radius)| frustum.check_sphere(center, *radius))
+                .map(|(entity, center, radius)| (entity, view.transform_point(&center), radius)),
+        );
+
+        let tiles_x = config.tiles_x();
+        let tiles_y = config.tiles_y();
+        let z_slices = config.z_slices;
+
+        clustered.tiles_x = tiles_x;
+        clustered.tiles_y = tiles_y;
+        clustered.z_slices = z_slices;
+        clustered.lights.clear();
+        clustered
+            .lights
+            .extend(self.lights.iter().map(|(entity, _, _)| *entity));
+        clustered.cluster_ranges.clear();
+        clustered.light_indices.clear();
+
+        for z in 0..z_slices {
+            let slice_near = near * (far / near).powf(z as f32 / z_slices as f32);
+            let slice_far = near * (far / near).powf((z + 1) as f32 / z_slices as f32);
+            for y in 0..tiles_y {
+                for x in 0..tiles_x {
+                    let aabb =
+                        cluster_aabb(&camera.proj, x, y, tiles_x, tiles_y, slice_near, slice_far);
+                    let offset = clustered.light_indices.len() as u32;
+                    clustered.light_indices.extend(
+                        self.lights
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, (_, center, radius))| {
+                                aabb.distance_squared_to(center) <= radius * radius
+                            })
+                            .map(|(index, _)| index as u32),
+                    );
+                    let count = clustered.light_indices.len() as u32 - offset;
+                    clustered.cluster_ranges.push((offset, count));
+                }
+            }
+        }
+    }
+}
+
+/// Near plane used for a spotlight's culling frustum. Arbitrary but small relative to
+/// any sane `range`; `range` is clamped to stay past it so `Perspective3::new` always
+/// sees `zfar > znear`.
+const SPOT_FRUSTUM_NEAR: f32 = 0.01;
+
+/// Builds a light-space frustum for a spotlight's cone. `angle` is the cone's
+/// half-angle, so the frustum's fov is `2 * angle`; a square frustum at that fov has a
+/// half-width equal to the cone's radius at every depth, so it fully circumscribes the
+/// circular cone cross-section with no under-coverage at the corners.
+///
+/// `Perspective3::new` panics if `fovy` isn't in `(0, pi)` or `zfar <= znear`, so `angle`
+/// is clamped well clear of both a zero-width and a 180 degree cone and `range` is
+/// clamped past the near plane before either reaches it.
+fn spot_frustum(light_transform: &GlobalTransform, angle: f32, range: f32) -> Frustum {
+    let half_angle = angle.max(0.01).min(FRAC_PI_2 - 0.01);
+    let far = range.max(SPOT_FRUSTUM_NEAR * 2.0);
+    let proj = Perspective3::new(1.0, half_angle * 2.0, SPOT_FRUSTUM_NEAR, far).to_homogeneous();
+    let view = light_transform.0.try_inverse().unwrap();
+    Frustum::new(proj * view)
+}
+
+/// Per-light shadow-caster visibility, indexed by the shadow-casting light's `Entity`.
+#[derive(Default)]
+pub struct ShadowVisibility {
+    pub casters: HashMap<Entity, BitSet>,
+}
+
+impl ShadowVisibility {
+    /// Caster bitset computed for `light` on the last run of `ShadowCasterVisibilitySystem`.
+    pub fn casters_for(&self, light: Entity) -> Option<&BitSet> {
+        self.casters.get(&light)
+    }
+}
+
+/// Fills `ShadowVisibility` each frame by culling potential shadow casters (every entity
+/// with a `GlobalTransform`, bounded by its `BoundingSphere` or, lacking one, a unit
+/// sphere at its origin) against each shadow-casting light's volume: a frustum for
+/// spot and directional lights, or a cheap sphere-vs-sphere range test for point lights.
+///
+/// Note that this should run after `GlobalTransform` has been updated for the current
+/// frame, and before shadow map rendering occurs.
+pub struct ShadowCasterVisibilitySystem {
+    casters: Vec<(Entity, Point3<f32>, f32)>,
+}
+
+impl ShadowCasterVisibilitySystem {
+    /// Create new shadow caster visibility system
+    pub fn new() -> Self {
+        ShadowCasterVisibilitySystem {
+            casters: Vec::default(),
+        }
+    }
+}
+
+impl Default for ShadowCasterVisibilitySystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> System<'a> for ShadowCasterVisibilitySystem {
+    type SystemData = (
+        Entities<'a>,
+        Write<'a, ShadowVisibility>,
+        Option<Read<'a, ActiveCamera>>,
+        Option<Read<'a, CascadeConfig>>,
+        ReadStorage<'a, Camera>,
+        ReadStorage<'a, Light>,
+        ReadStorage<'a, GlobalTransform>,
+        ReadStorage<'a, BoundingSphere>,
+    );
+
+    fn run(
+        &mut self,
+        (entities, mut shadows, active, cascade_config, camera, light, global, bound): Self::SystemData,
+    ) {
+        #[cfg(feature = "profiler")]
+        profile_scope!("run");
+
+        let cascade_config = cascade_config.as_deref().copied().unwrap_or_default();
+        let defcam = Camera::standard_2d();
+        let identity = GlobalTransform::default();
+
+        let mut camera_join = (&camera, &global).join();
+        let (active_camera, active_camera_transform) = active
+            .and_then(|a| camera_join.get(a.entity, &entities))
+            .or_else(|| camera_join.next())
+            .unwrap_or((&defcam, &identity));
+
+        self.casters.clear();
+        self.casters
+            .extend(
+                (&*entities, &global, bound.maybe())
+                    .join()
+                    .map(|(entity, global, sphere)| {
+                        let center = global
+                            .0
+                            .transform_point(&sphere.map_or(Point3::origin(), |s| s.center));
+                        let radius = sphere.map_or(1.0, |s| s.radius)
+                            * global.0[(0, 0)].max(global.0[(1, 1)]).max(global.0[(2, 2)]);
+                        (entity, center, radius)
+                    }),
+            );
+
+        shadows.casters.clear();
+        for (light_entity, light, light_transform) in (&entities, &light, &global).join() {
+            let caster_ids = match light {
+                Light::Spot(spot) => {
+                    let frustum = spot_frustum(light_transform, spot.angle, spot.range);
+                    self.casters
+                        .iter()
+                        .filter(|(_, center, radius)| frustum.check_sphere(center, *radius))
+                        .map(|(entity, _, _)| entity.id())
+                        .collect()
+                }
+                Light::Directional(_) => {
+                    // Cull against the camera-derived cascade frusta, not an AABB fit
+                    // around these same casters: that would make every caster visible
+                    // by construction and cull nothing.
+                    match compute_cascades(
+                        active_camera,
+                        active_camera_transform,
+                        light_transform,
+                        &cascade_config,
+                    ) {
+                        Some(cascades) => {
+                            // The cascade frusta are in the light's view space.
+                            let light_view = light_transform.0.try_inverse().unwrap();
+                            self.casters
+                                .iter()
+                                .filter(|(_, center, radius)| {
+                                    let center = light_view.transform_point(center);
+                                    cascades
+                                        .frusta
+                                        .iter()
+                                        .any(|frustum| frustum.check_sphere(&center, *radius))
+                                })
+                                .map(|(entity, _, _)| entity.id())
+                                .collect()
+                        }
+                        // No perspective camera to derive cascades from this frame (e.g.
+                        // the only active camera is orthographic); skip the cull and keep
+                        // every caster rather than cast no shadows at all.
+                        None => self
+                            .casters
+                            .iter()
+                            .map(|(entity, _, _)| entity.id())
+                            .collect(),
+                    }
+                }
+                Light::Point(point) => {
+                    let light_pos = light_transform.0.transform_point(&Point3::origin());
+                    self.casters
+                        .iter()
+                        .filter(|(_, center, radius)| {
+                            let max_distance = point.radius + radius;
+                            distance_squared(&light_pos, center) <= max_distance * max_distance
+                        })
+                        .map(|(entity, _, _)| entity.id())
+                        .collect()
+                }
+            };
+            shadows.casters.insert(light_entity, caster_ids);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a projection matrix using the zero-to-one depth convention
+    /// `perspective_near_far` assumes, independent of nalgebra's own `Perspective3`
+    /// (which defaults to OpenGL's `[-1, 1]` depth range).
+    fn zero_to_one_perspective(fovy: f32, aspect: f32, near: f32, far: f32) -> Matrix4<f32> {
+        let f = 1.0 / (fovy / 2.0).tan();
+        let mut m = Matrix4::zeros();
+        m[(0, 0)] = f / aspect;
+        m[(1, 1)] = f;
+        m[(2, 2)] = far / (near - far);
+        m[(2, 3)] = near * far / (near - far);
+        m[(3, 2)] = -1.0;
+        m
+    }
+
+    #[test]
+    fn perspective_near_far_recovers_clip_planes() {
+        let proj = zero_to_one_perspective(std::f32::consts::FRAC_PI_4, 1.0, 0.1, 100.0);
+        let (near, far) = perspective_near_far(&proj).unwrap();
+        assert!((near - 0.1).abs() < 1e-4);
+        assert!((far - 100.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn perspective_near_far_rejects_orthographic_projections() {
+        let proj = Orthographic3::new(-1.0, 1.0, -1.0, 1.0, 0.1, 100.0).to_homogeneous();
+        assert_eq!(perspective_near_far(&proj), None);
+    }
+
+    #[test]
+    fn cluster_aabb_distance_squared_to_point() {
+        let aabb = ClusterAabb {
+            min: Point3::new(-1.0, -1.0, -1.0),
+            max: Point3::new(1.0, 1.0, 1.0),
+        };
+        assert_eq!(aabb.distance_squared_to(&Point3::origin()), 0.0);
+        assert_eq!(aabb.distance_squared_to(&Point3::new(1.0, 1.0, 1.0)), 0.0);
+        assert_eq!(aabb.distance_squared_to(&Point3::new(3.0, 0.0, 0.0)), 4.0);
+        assert_eq!(
+            aabb.distance_squared_to(&Point3::new(3.0, 2.0, -1.0)),
+            4.0 + 1.0
+        );
+    }
+
+    #[test]
+    fn cascade_splits_are_monotonic_and_span_near_to_far() {
+        let config = CascadeConfig {
+            count: 4,
+            lambda: 0.5,
+        };
+        let splits = cascade_splits(0.1, 100.0, &config);
+
+        assert_eq!(splits.len(), config.count + 1);
+        assert!((splits[0] - 0.1).abs() < 1e-5);
+        assert!((splits[config.count] - 100.0).abs() < 1e-3);
+        for window in splits.windows(2) {
+            assert!(window[1] > window[0]);
+        }
+    }
+
+    #[test]
+    fn check_aabb_matches_check_sphere_at_the_frustum_boundary() {
+        let proj = zero_to_one_perspective(std::f32::consts::FRAC_PI_2, 1.0, 0.1, 100.0);
+        let frustum = Frustum::new(proj);
+
+        // Comfortably inside the frustum on every axis.
+        assert!(frustum.check_aabb(&Point3::new(0.0, 0.0, -5.0), &Vector3::new(0.5, 0.5, 0.5)));
+        // Behind the camera: outside regardless of box size.
+        assert!(!frustum.check_aabb(&Point3::new(0.0, 0.0, 5.0), &Vector3::new(0.5, 0.5, 0.5)));
+        // Far enough to the side that even the half-extent doesn't reach back in.
+        assert!(!frustum.check_aabb(
+            &Point3::new(1000.0, 0.0, -5.0),
+            &Vector3::new(0.5, 0.5, 0.5)
+        ));
+        // A box whose center is outside the frustum (behind the camera) but whose
+        // half-extents reach back into it gets the same verdict as a sphere would.
+        let just_outside = frustum.check_sphere(&Point3::new(0.0, 0.0, 1000.0), 2000.0);
+        let aabb_equivalent = frustum.check_aabb(
+            &Point3::new(0.0, 0.0, 1000.0),
+            &Vector3::new(2000.0, 2000.0, 2000.0),
+        );
+        assert_eq!(just_outside, aabb_equivalent);
+    }
+
+    #[test]
+    fn world_extents_account_for_rotation_and_non_uniform_scale() {
+        let bbox = BoundingBox::new(Point3::origin(), Vector3::new(1.0, 0.5, 0.25));
+
+        // Rotate 90 degrees about Z (swaps local x/y into world y/x), then stretch the
+        // (now world-x) axis by 2x. A naive "largest diagonal entry" scalar radius would
+        // miss both the swap and the non-uniform stretch.
+        let rotation = amethyst_core::math::Rotation3::from_euler_angles(
+            0.0,
+            0.0,
+            std::f32::consts::FRAC_PI_2,
+        )
+        .to_homogeneous();
+        let scale = Matrix4::new_nonuniform_scaling(&Vector3::new(2.0, 1.0, 1.0));
+        let transform = GlobalTransform(scale * rotation);
+
+        let (_, extents) = bbox.world_extents(&transform);
+        assert!((extents.x - 1.0).abs() < 1e-5);
+        assert!((extents.y - 1.0).abs() < 1e-5);
+        assert!((extents.z - 0.25).abs() < 1e-5);
+    }
 }